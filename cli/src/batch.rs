@@ -0,0 +1,176 @@
+//! Concurrent batch scraping of many dossiers, bounded by a semaphore and a
+//! minimum delay between request starts so we stay polite to
+//! echa.europa.eu.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::output::{self, DossierReport, OutputFormat};
+use scrapper::validate::parse_dossier_target;
+use scrapper::{scrape_all, Section, Subsection};
+use tokio::sync::{Mutex, Semaphore};
+
+/// Outcome of scraping a single dossier: either the output path it was
+/// written to, or the reason it failed.
+pub struct DossierOutcome {
+    pub dossier: String,
+    pub result: Result<PathBuf, String>,
+}
+
+/// Enforces a minimum spacing between request starts across however many
+/// tasks are running concurrently.
+struct RateLimiter {
+    min_delay: Duration,
+    last_start: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    fn new(min_delay: Duration) -> Self {
+        RateLimiter {
+            min_delay,
+            last_start: Mutex::new(None),
+        }
+    }
+
+    async fn wait_turn(&self) {
+        let mut last_start = self.last_start.lock().await;
+        if let Some(previous) = *last_start {
+            let elapsed = previous.elapsed();
+            if elapsed < self.min_delay {
+                tokio::time::sleep(self.min_delay - elapsed).await;
+            }
+        }
+        *last_start = Some(Instant::now());
+    }
+}
+
+/// Scrape every target (a dossier id or a full url) concurrently, at most
+/// `concurrency` at a time, writing one output file per dossier into
+/// `output_dir` via the [`OutputBackend`](crate::output::OutputBackend) for
+/// `format`. Returns a per-dossier succeeded/failed summary rather than
+/// aborting the whole run on one failure.
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    targets: Vec<String>,
+    output_dir: PathBuf,
+    cache_path: Option<PathBuf>,
+    concurrency: usize,
+    min_delay: Duration,
+    no_cache: bool,
+    format: OutputFormat,
+    report_config: Option<PathBuf>,
+) -> Vec<DossierOutcome> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let limiter = Arc::new(RateLimiter::new(min_delay));
+    let mut handles = Vec::with_capacity(targets.len());
+
+    for target in targets {
+        let semaphore = Arc::clone(&semaphore);
+        let limiter = Arc::clone(&limiter);
+        let output_dir = output_dir.clone();
+        let cache_path = cache_path.clone();
+        let report_config = report_config.clone();
+
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("batch semaphore was closed early");
+            limiter.wait_turn().await;
+            scrape_one(target, output_dir, cache_path, no_cache, format, report_config).await
+        }));
+    }
+
+    let mut outcomes = Vec::with_capacity(handles.len());
+    for handle in handles {
+        outcomes.push(handle.await.unwrap_or_else(|error| DossierOutcome {
+            dossier: "<unknown>".to_string(),
+            result: Err(format!("Scrape task panicked: {error}")),
+        }));
+    }
+    outcomes
+}
+
+async fn scrape_one(
+    target: String,
+    output_dir: PathBuf,
+    cache_path: Option<PathBuf>,
+    no_cache: bool,
+    format: OutputFormat,
+    report_config: Option<PathBuf>,
+) -> DossierOutcome {
+    let dossier_ref = match parse_dossier_target(&target) {
+        Ok(dossier_ref) => dossier_ref,
+        Err(error) => {
+            return DossierOutcome {
+                dossier: target,
+                result: Err(error.to_string()),
+            }
+        }
+    };
+
+    let result = write_dossier(
+        dossier_ref.canonical_url,
+        &dossier_ref.id,
+        output_dir,
+        cache_path,
+        no_cache,
+        format,
+        report_config,
+    )
+    .await;
+
+    DossierOutcome {
+        dossier: dossier_ref.id,
+        result: result.map_err(|error| error.to_string()),
+    }
+}
+
+async fn write_dossier(
+    url: String,
+    dossier: &str,
+    output_dir: PathBuf,
+    cache_path: Option<PathBuf>,
+    no_cache: bool,
+    format: OutputFormat,
+    report_config: Option<PathBuf>,
+) -> anyhow::Result<PathBuf> {
+    let sections = scrape_all(url, cache_path, no_cache).await?;
+
+    let identification = sections
+        .get(&Section::Identification)
+        .cloned()
+        .unwrap_or_default();
+    let boundary = sections
+        .get(&Section::Composition(Subsection::Boundary))
+        .cloned()
+        .unwrap_or_default();
+    let legal_entity = sections
+        .get(&Section::Composition(Subsection::LegalEntity))
+        .cloned()
+        .unwrap_or_default();
+    let generated = sections
+        .get(&Section::Composition(Subsection::Generated))
+        .cloned()
+        .unwrap_or_default();
+    let other = sections
+        .get(&Section::Composition(Subsection::Other))
+        .cloned()
+        .unwrap_or_default();
+
+    let backend = output::backend_for(format, report_config.as_deref())?;
+    let ofile_path = output_dir.join(dossier).with_extension(backend.extension());
+
+    let report = DossierReport {
+        dossier_id: dossier,
+        identification: &identification,
+        boundary: &boundary,
+        legal_entity: &legal_entity,
+        generated: &generated,
+        other: &other,
+    };
+    backend.write(&report, &ofile_path)?;
+
+    Ok(ofile_path)
+}
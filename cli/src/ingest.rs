@@ -0,0 +1,83 @@
+//! CLI-side glue for the persistent dossier [`Store`]: bulk-importing
+//! previously saved TSV outputs with `walkdir`, and running ad-hoc queries
+//! against it.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use scrapper::store::{QueryFilter, Store};
+use scrapper::Record;
+use walkdir::WalkDir;
+
+/// Walk `dir` for every `*.tsv` dossier output (named `<dossier id>.tsv`,
+/// the way the one-shot CLI flow and `--format tsv` write them) and insert
+/// its rows into the store at `store_path`, keyed by each file's stem as
+/// the dossier id.
+pub fn import_dir(dir: &Path, store_path: &Path) -> Result<()> {
+    let mut store = Store::open(store_path)?;
+    let mut imported_files = 0;
+    let mut imported_rows = 0;
+
+    for entry in WalkDir::new(dir).into_iter().filter_map(|entry| entry.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("tsv") {
+            continue;
+        }
+
+        let dossier_id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        match read_tsv(path) {
+            Ok(records) => match store.insert_all(&dossier_id, &records) {
+                Ok(count) => {
+                    imported_files += 1;
+                    imported_rows += count;
+                }
+                Err(error) => eprintln!("Couldn't store rows from {}: {error}", path.display()),
+            },
+            Err(error) => eprintln!("Couldn't read {}: {error}", path.display()),
+        }
+    }
+
+    println!(
+        "Imported {imported_rows} row(s) from {imported_files} file(s) into {}",
+        store_path.display()
+    );
+    Ok(())
+}
+
+fn read_tsv(path: &Path) -> Result<Vec<Record>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .from_path(path)
+        .with_context(|| format!("Couldn't open {}", path.display()))?;
+
+    reader
+        .deserialize()
+        .collect::<Result<Vec<Record>, csv::Error>>()
+        .with_context(|| format!("Couldn't parse {} as a dossier TSV", path.display()))
+}
+
+/// Run `filter` against the store at `store_path` and print matching rows
+/// as TSV to stdout.
+pub fn query(store_path: &Path, filter: QueryFilter) -> Result<()> {
+    let store = Store::open(store_path)?;
+    let rows = store.query(&filter)?;
+
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .from_writer(std::io::stdout());
+    for row in &rows {
+        wtr.serialize(row)?;
+    }
+    wtr.flush()?;
+
+    eprintln!("{} matching row(s)", rows.len());
+    Ok(())
+}
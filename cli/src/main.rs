@@ -1,116 +1,367 @@
-use anyhow::{anyhow, Context, Result};
-use directories::{ProjectDirs, UserDirs};
-use oxychem::{get_cas, get_cid, search_formula};
+mod batch;
+mod ingest;
+mod output;
+mod sdf;
+mod server;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use directories::UserDirs;
+use output::{DossierReport, OutputFormat};
+use scrapper::store::{QueryFilter, Store};
+use scrapper::validate::parse_dossier_url;
 use scrapper::Subsection::{Boundary, Other};
-use scrapper::{EchaSite, Section, Subsection};
+use scrapper::{EchaSite, Record, Section, Subsection};
 use serde::{Deserialize, Serialize};
-use std::borrow::{Borrow, Cow};
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEFAULT_DOSSIER_URL: &str =
+    "https://echa.europa.eu/registration-dossier/-/registered-dossier/24529";
+
+/// Scrape ECHA registered-dossier pages.
+#[derive(Parser, Debug)]
+#[command(name = "echars", about = "Scrape ECHA registered-dossier pages")]
+struct Cli {
+    /// Dossier url to scrape. Ignored when --input-file is set; falls back to
+    /// a sample dossier when neither is given.
+    url: Option<String>,
+
+    /// Read many dossier urls from this file (one per line) and scrape them
+    /// in sequence.
+    #[arg(long)]
+    input_file: Option<PathBuf>,
+
+    /// Directory to write output files into. Defaults to
+    /// `~/Desktop/echars_output`.
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// Output format for each scraped dossier.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Tsv)]
+    format: OutputFormat,
+
+    /// YAML config for the HTML report's title/logo (e.g. `title: "Acme QA"`,
+    /// `logo: "https://example.com/logo.png"`). Only consulted for
+    /// `--format html`.
+    #[arg(long)]
+    report_config: Option<PathBuf>,
+
+    /// Ignore cached sections and re-scrape everything, bypassing both the
+    /// persistent dossier cache and the on-disk content cache for fetched
+    /// pages and PubChem lookups.
+    #[arg(long)]
+    refresh: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a long-running HTTP service exposing scraped dossiers as JSON.
+    Server {
+        /// Port to listen on.
+        #[arg(default_value_t = 8080)]
+        port: u16,
+    },
+    /// Scrape many dossiers concurrently, bounded by --concurrency.
+    Batch {
+        /// A file path (one dossier id/url per line) or a comma-separated list.
+        targets: String,
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+        #[arg(long, default_value_t = 500)]
+        delay_ms: u64,
+    },
+    /// Follow each constituent's weblink into a cross-dossier graph.
+    Graph {
+        url: String,
+        #[arg(long, default_value_t = 1)]
+        max_depth: usize,
+    },
+    /// Bulk-import previously saved TSV dossier outputs into the persistent
+    /// SQLite store.
+    Import {
+        /// Directory to recursively scan for `*.tsv` dossier outputs.
+        dir: PathBuf,
+    },
+    /// Query the persistent store, e.g. every substance with a given CAS
+    /// across every dossier scraped by the one-shot flow or imported.
+    /// `batch`, `graph` and `server` runs don't write to the store.
+    Query {
+        #[arg(long)]
+        cas: Option<String>,
+        #[arg(long)]
+        formula: Option<String>,
+        #[arg(long)]
+        section: Option<String>,
+    },
+}
+
+/// Resolve the directory output files are written into: `--output-dir` if
+/// given, otherwise `~/Desktop/echars_output`.
+fn resolve_output_dir(explicit: Option<PathBuf>) -> Result<PathBuf> {
+    if let Some(dir) = explicit {
+        return Ok(dir);
+    }
+
+    UserDirs::new()
+        .and_then(|dirs| dirs.desktop_dir().map(|path| path.join("echars_output")))
+        .context("Couldn't determine a default output folder; pass --output-dir")
+}
 
 fn main() -> Result<()> {
-    // **************************************************
-    // ************ CLI args requirements ***************
-    // **************************************************
-    let url: Cow<'static, str> = match std::env::args().nth(1) {
-        Some(url) => Cow::from(url),
-        None => {
+    let cli = Cli::parse();
+
+    let output_dir = resolve_output_dir(cli.output_dir)?;
+    fs::create_dir_all(&output_dir)?;
+    let cache_path = output_dir.join("cache.db");
+    let store_path = output_dir.join("store.db");
+
+    match cli.command {
+        Some(Command::Server { port }) => {
+            let addr = SocketAddr::from(([127, 0, 0, 1], port));
+            return tokio::runtime::Runtime::new()
+                .context("Couldn't start Tokio runtime")?
+                .block_on(server::serve(addr, Some(cache_path), cli.refresh));
+        }
+        Some(Command::Batch {
+            targets,
+            concurrency,
+            delay_ms,
+        }) => {
+            return run_batch(
+                &targets,
+                output_dir,
+                cache_path,
+                concurrency,
+                delay_ms,
+                cli.refresh,
+                cli.format,
+                cli.report_config.clone(),
+            )
+        }
+        Some(Command::Graph { url, max_depth }) => {
+            return run_graph(
+                &url,
+                output_dir,
+                cache_path,
+                max_depth,
+                cli.refresh,
+                cli.format,
+                cli.report_config.as_deref(),
+            )
+        }
+        Some(Command::Import { dir }) => return ingest::import_dir(&dir, &store_path),
+        Some(Command::Query {
+            cas,
+            formula,
+            section,
+        }) => return ingest::query(&store_path, QueryFilter { cas, formula, section }),
+        None => {}
+    }
+
+    let urls: Vec<String> = if let Some(input_file) = &cli.input_file {
+        fs::read_to_string(input_file)
+            .with_context(|| format!("Couldn't read --input-file {}", input_file.display()))?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()
+    } else {
+        vec![cli.url.unwrap_or_else(|| {
             println!("No CLI URL provided, using default.");
-            Cow::from("https://echa.europa.eu/registration-dossier/-/registered-dossier/24529")
-            //"https://echa.europa.eu/registration-dossier/-/registered-dossier/26453".into()
+            DEFAULT_DOSSIER_URL.to_string()
+        })]
+    };
+
+    for url in urls {
+        scrape_one_to_file(
+            &url,
+            &output_dir,
+            &cache_path,
+            &store_path,
+            cli.refresh,
+            cli.format,
+            cli.report_config.as_deref(),
+        )?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_batch(
+    targets_arg: &str,
+    output_dir: PathBuf,
+    cache_path: PathBuf,
+    concurrency: usize,
+    delay_ms: u64,
+    no_cache: bool,
+    format: OutputFormat,
+    report_config: Option<PathBuf>,
+) -> Result<()> {
+    let targets: Vec<String> = if Path::new(targets_arg).is_file() {
+        fs::read_to_string(targets_arg)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()
+    } else {
+        targets_arg
+            .split(',')
+            .map(str::trim)
+            .filter(|target| !target.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    let outcomes = tokio::runtime::Runtime::new()
+        .context("Couldn't start Tokio runtime")?
+        .block_on(batch::run(
+            targets,
+            output_dir,
+            Some(cache_path),
+            concurrency,
+            Duration::from_millis(delay_ms),
+            no_cache,
+            format,
+            report_config,
+        ));
+
+    let failed = outcomes.iter().filter(|outcome| outcome.result.is_err()).count();
+    for outcome in &outcomes {
+        match &outcome.result {
+            Ok(path) => println!("{}: wrote {}", outcome.dossier, path.display()),
+            Err(reason) => println!("{}: FAILED - {reason}", outcome.dossier),
         }
+    }
+    println!("{} succeeded, {failed} failed", outcomes.len() - failed);
+
+    Ok(())
+}
+
+/// Write a [`scrapper::graph::crawl`] result's records through the same
+/// [`output::backend_for`] every other scrape flow uses. The crawl spans
+/// many dossiers and doesn't keep each record's originating section, so
+/// unlike [`scrape_one_to_file`] every record goes into the report's
+/// `identification` slot, leaving the other four empty — for
+/// [`OutputFormat::Tsv`] this is indistinguishable from before, since that
+/// backend only concatenates the five slices back together anyway.
+fn run_graph(
+    url: &str,
+    output_dir: PathBuf,
+    cache_path: PathBuf,
+    max_depth: usize,
+    no_cache: bool,
+    format: OutputFormat,
+    report_config: Option<&Path>,
+) -> Result<()> {
+    let dossier_ref =
+        parse_dossier_url(url).with_context(|| format!("'{url}' isn't a usable ECHA dossier url"))?;
+
+    let crawl_result = scrapper::graph::crawl(
+        &dossier_ref.canonical_url,
+        Some(cache_path),
+        max_depth,
+        no_cache,
+    )?;
+
+    let dossier = dossier_ref.id;
+    let backend = output::backend_for(format, report_config)?;
+    let ofile_path = output_dir.join(&dossier).with_extension(backend.extension());
+
+    let report = DossierReport {
+        dossier_id: &dossier,
+        identification: &crawl_result.records,
+        boundary: &[],
+        legal_entity: &[],
+        generated: &[],
+        other: &[],
     };
+    backend.write(&report, &ofile_path)?;
 
-    // **************************************************
-    // ************ Create folder & files ***************
-    // **************************************************
-    let mut ofile_path = PathBuf::new();
-    if let Some(user_dirs) = UserDirs::new() {
-        let output_dir = user_dirs
-            .desktop_dir()
-            .map(|path| path.join("echars_output"))
-            .context("Couldn't create output folder path")?;
-
-        // Output file name is the number of the dossier in the url.
-        // The file is truncated if it already exists.
-        let dossier = url
-            .split('/')
-            .last()
-            .expect("Couldn't obtain dossier number");
-
-        ofile_path = output_dir.join(dossier).with_extension("tsv");
-
-        fs::create_dir_all(output_dir)?; //.expect("Couldn't create output folder path");
+    let graph_path = output_dir.join(format!("{dossier}.graph.json"));
+    fs::write(&graph_path, serde_json::to_string_pretty(&crawl_result.adjacency)?)?;
+
+    println!(
+        "Wrote {} records to {}",
+        crawl_result.records.len(),
+        ofile_path.display()
+    );
+    println!("Wrote adjacency graph to {}", graph_path.display());
+
+    Ok(())
+}
+
+fn scrape_one_to_file(
+    url: &str,
+    output_dir: &Path,
+    cache_path: &Path,
+    store_path: &Path,
+    refresh: bool,
+    format: OutputFormat,
+    report_config: Option<&Path>,
+) -> Result<()> {
+    let dossier_ref = parse_dossier_url(url)
+        .with_context(|| format!("'{url}' isn't a usable ECHA dossier url"))?;
+
+    let backend = output::backend_for(format, report_config)?;
+    let ofile_path = output_dir
+        .join(&dossier_ref.id)
+        .with_extension(backend.extension());
+
+    let mut echa = EchaSite::new(&dossier_ref.canonical_url, Some(cache_path), refresh);
+    if refresh {
+        echa.set_cache_ttl_days(0);
     }
 
-    // **************************************************
-    // **************** Getting data ********************
-    // **************************************************
-    let mut echa = EchaSite::new(url.borrow());
     let identification = echa.get_constituents(Section::Identification);
     let boundary = echa.get_constituents(Section::Composition(Subsection::Boundary));
     let legal = echa.get_constituents(Section::Composition(Subsection::LegalEntity));
     let generated = echa.get_constituents(Section::Composition(Subsection::Generated));
     let other = echa.get_constituents(Section::Composition(Subsection::Other));
 
-    // **************************************************
-    // ****************** Save data *********************
-    // **************************************************
-    let mut wtr = csv::WriterBuilder::new()
-        .delimiter(b'\t')
-        .from_path(ofile_path)?;
-
-    for data in identification {
-        // ! TODO: Compare cas with pubchem data and retrieve sdf file.
-        // let formula = data.formula.clone();
-        // let list = match search_formula(&formula) {
-        //     Ok(it) => it,
-        //     Err(_err) => {//
-        //         return Err(anyhow!(//
-        //             "Couldn't obtain list of cids fr// om molecular formula -> {_err}"
-        //         ))//
-        //     }//
-        // };//
-
-        // //let cid = get_cid(data.substance.clone()).// unwrap_or(0);
-        // let cas_list: Vec<String> = list//
-        //     .iter()//
-        //     .map(|cid| cid.parse::<isize>().expect("// Couldn't parse cid to isize"))
-        //     .map(|cid| get_cas(cid).unwrap_or("N/A".// to_string()))
-        //     .collect();//
-
-        // let w = cas_list.iter()//
-        //     .enumerate()//
-        //     .scan(0, |state, (idx, value)| {//
-        //         if value == &data.cas {//
-        //             *state = idx;//
-        //         }//
-        //         Some(*state)//
-        //     });//
-
-        // dbg!(w);//
-
-        // //let cas = get_cas(cid).unwrap_or("N/A".to_// string());
-        // eprintln!("list = {:#?}", list);
-        // eprintln!("CAS\n\tPubchem: {:?}\n\tEcha: {:?}", cas_list, &data.cas);
-        wtr.serialize(data)?;
+    if let Err(error) = sdf::fetch_sdfs(&identification, output_dir, refresh) {
+        eprintln!("Couldn't complete PubChem SDF retrieval: {error}");
     }
 
-    for data in boundary {
-        wtr.serialize(data)?;
+    if let Err(error) = persist_to_store(
+        store_path,
+        &dossier_ref.id,
+        [&identification, &boundary, &legal, &generated, &other],
+    ) {
+        eprintln!("Couldn't persist scraped constituents to the store: {error}");
     }
 
-    for data in legal {
-        wtr.serialize(data)?;
-    }
-
-    for data in generated {
-        wtr.serialize(data)?;
-    }
+    let report = DossierReport {
+        dossier_id: &dossier_ref.id,
+        identification: &identification,
+        boundary: &boundary,
+        legal_entity: &legal,
+        generated: &generated,
+        other: &other,
+    };
+    backend.write(&report, &ofile_path)
+}
 
-    for data in other {
-        wtr.serialize(data)?;
+/// Insert every scraped section for `dossier_id` into the persistent store
+/// at `store_path`, so the dossier stays queryable (by CAS, formula,
+/// section) even after this run's output file is archived or deleted.
+fn persist_to_store(store_path: &Path, dossier_id: &str, sections: [&[Record]; 5]) -> Result<()> {
+    let mut store = Store::open(store_path)?;
+    let mut total = 0;
+    for section in sections {
+        total += store.insert_all(dossier_id, section)?;
     }
-
+    println!(
+        "Stored {total} constituent(s) for dossier {dossier_id} in {}",
+        store_path.display()
+    );
     Ok(())
 }
@@ -0,0 +1,232 @@
+//! Pluggable output backends for a scraped dossier's sections.
+//!
+//! `--format` selects an [`OutputBackend`] instead of `main` serializing
+//! straight to a `csv::Writer`, so a new format is a new impl here rather
+//! than another branch threaded through the scrape flow.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use scrapper::validate::is_well_formed_http_url;
+use scrapper::Record;
+use serde::{Deserialize, Serialize};
+
+const HTML_TEMPLATE: &str = include_str!("../templates/report.html.liquid");
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum OutputFormat {
+    Tsv,
+    Json,
+    Html,
+}
+
+/// Every section scraped for one dossier, in the order the TSV/HTML output
+/// has always listed them.
+pub struct DossierReport<'a> {
+    pub dossier_id: &'a str,
+    pub identification: &'a [Record],
+    pub boundary: &'a [Record],
+    pub legal_entity: &'a [Record],
+    pub generated: &'a [Record],
+    pub other: &'a [Record],
+}
+
+/// Writes a [`DossierReport`] to `path` in some output format.
+pub trait OutputBackend {
+    /// File extension (without the dot) this backend writes, used to name
+    /// the output file alongside the dossier id.
+    fn extension(&self) -> &'static str;
+
+    fn write(&self, report: &DossierReport, path: &Path) -> Result<()>;
+}
+
+/// Build the [`OutputBackend`] for `format`. `report_header_config` is only
+/// consulted for [`OutputFormat::Html`].
+pub fn backend_for(
+    format: OutputFormat,
+    report_header_config: Option<&Path>,
+) -> Result<Box<dyn OutputBackend>> {
+    match format {
+        OutputFormat::Tsv => Ok(Box::new(TsvBackend)),
+        OutputFormat::Json => Ok(Box::new(JsonBackend)),
+        OutputFormat::Html => Ok(Box::new(HtmlBackend::new(report_header_config)?)),
+    }
+}
+
+/// One flat TSV, sections concatenated in the same order as
+/// [`DossierReport`], matching the layout the CLI has always written.
+struct TsvBackend;
+
+impl OutputBackend for TsvBackend {
+    fn extension(&self) -> &'static str {
+        "tsv"
+    }
+
+    fn write(&self, report: &DossierReport, path: &Path) -> Result<()> {
+        let mut wtr = csv::WriterBuilder::new().delimiter(b'\t').from_path(path)?;
+        for section in [
+            report.identification,
+            report.boundary,
+            report.legal_entity,
+            report.generated,
+            report.other,
+        ] {
+            for record in section {
+                wtr.serialize(record)?;
+            }
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// All sections as one JSON object, keyed by section name, keeping each
+/// section's records distinct instead of flattening them like the TSV does.
+struct JsonBackend;
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    dossier_id: &'a str,
+    identification: &'a [Record],
+    boundary: &'a [Record],
+    legal_entity: &'a [Record],
+    generated: &'a [Record],
+    other: &'a [Record],
+}
+
+impl OutputBackend for JsonBackend {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn write(&self, report: &DossierReport, path: &Path) -> Result<()> {
+        let payload = JsonReport {
+            dossier_id: report.dossier_id,
+            identification: report.identification,
+            boundary: report.boundary,
+            legal_entity: report.legal_entity,
+            generated: report.generated,
+            other: report.other,
+        };
+        fs::write(path, serde_json::to_string_pretty(&payload)?).context("Couldn't write JSON report")
+    }
+}
+
+/// Title/logo shown at the top of an [`HtmlBackend`] report, loaded from an
+/// optional YAML config so lab/compliance teams can brand it without
+/// touching the template.
+#[derive(Debug, Clone, Deserialize)]
+struct ReportHeader {
+    #[serde(default = "default_title")]
+    title: String,
+    #[serde(default)]
+    logo: Option<String>,
+}
+
+impl Default for ReportHeader {
+    fn default() -> Self {
+        ReportHeader {
+            title: default_title(),
+            logo: None,
+        }
+    }
+}
+
+fn default_title() -> String {
+    "ECHA Dossier Report".to_string()
+}
+
+impl ReportHeader {
+    fn load(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read report header config {}", path.display()))?;
+        serde_yaml::from_str(&raw)
+            .with_context(|| format!("Couldn't parse report header config {}", path.display()))
+    }
+}
+
+/// Renders the collected constituents into a styled, shareable report via a
+/// Liquid template, instead of only a spreadsheet-oriented file.
+struct HtmlBackend {
+    header: ReportHeader,
+}
+
+impl HtmlBackend {
+    fn new(header_config: Option<&Path>) -> Result<Self> {
+        Ok(HtmlBackend {
+            header: ReportHeader::load(header_config)?,
+        })
+    }
+}
+
+/// Convert a section's records to a Liquid value. Each row carries the same
+/// serialized field names as the TSV/JSON backends (e.g. `Reference CAS`),
+/// plus a `formula` key, which those backends skip.
+///
+/// `weblink` is scraped verbatim from an `<a href>` attribute and the
+/// template renders it both inside an `href="..."` attribute and as link
+/// text, so a record whose `weblink` doesn't parse as a well-formed
+/// `http`/`https` url has it blanked out here rather than risking a
+/// malformed value breaking out of the attribute.
+fn section_value(records: &[Record]) -> Result<liquid::model::Value> {
+    let rows = records
+        .iter()
+        .cloned()
+        .map(|mut record| {
+            if !is_well_formed_http_url(&record.weblink) {
+                record.weblink.clear();
+            }
+            let mut row = liquid::model::to_object(&record)
+                .context("Couldn't convert section records for the report template")?;
+            row.insert("formula".into(), liquid::model::Value::scalar(record.formula));
+            Ok(liquid::model::Value::Object(row))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(liquid::model::Value::Array(rows))
+}
+
+impl OutputBackend for HtmlBackend {
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn write(&self, report: &DossierReport, path: &Path) -> Result<()> {
+        let parser = liquid::ParserBuilder::with_stdlib()
+            .build()
+            .context("Couldn't build report template parser")?;
+        let template = parser
+            .parse(HTML_TEMPLATE)
+            .context("Couldn't parse report template")?;
+
+        let mut globals = liquid::Object::new();
+        globals.insert("title".into(), liquid::model::Value::scalar(self.header.title.clone()));
+        globals.insert(
+            "logo".into(),
+            self.header
+                .logo
+                .clone()
+                .map(liquid::model::Value::scalar)
+                .unwrap_or(liquid::model::Value::Nil),
+        );
+        globals.insert(
+            "dossier_id".into(),
+            liquid::model::Value::scalar(report.dossier_id.to_string()),
+        );
+        globals.insert("identification".into(), section_value(report.identification)?);
+        globals.insert("boundary".into(), section_value(report.boundary)?);
+        globals.insert("legal_entity".into(), section_value(report.legal_entity)?);
+        globals.insert("generated".into(), section_value(report.generated)?);
+        globals.insert("other".into(), section_value(report.other)?);
+
+        let rendered = template
+            .render(&globals)
+            .context("Couldn't render report template")?;
+        fs::write(path, rendered).context("Couldn't write HTML report")
+    }
+}
@@ -0,0 +1,240 @@
+//! Finishes the PubChem cross-check this tool always meant to do: for each
+//! Identification constituent, reconcile its CAS against PubChem and
+//! download the matching compound's SDF record, with a SHA-512 digest
+//! manifest so a later run can trust an on-disk SDF instead of re-fetching.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use oxychem::{get_cas, search_formula};
+use scrapper::content_cache::{self, ContentCache};
+use scrapper::Record;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+
+const MANIFEST_FILE: &str = "sdf_manifest.json";
+
+/// One verified SDF download: which CID it came from, where it's stored,
+/// and the digest to check it against before reusing it.
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestEntry {
+    cas: String,
+    cid: isize,
+    path: PathBuf,
+    digest: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?).context("Couldn't write SDF manifest")
+    }
+
+    fn find(&self, cas: &str) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|entry| entry.cas == cas)
+    }
+
+    fn upsert(&mut self, entry: ManifestEntry) {
+        self.entries.retain(|existing| existing.cas != entry.cas);
+        self.entries.push(entry);
+    }
+}
+
+/// A Subresource-Integrity-style digest over `bytes`: `sha512-<base64>`.
+fn sha512_digest(bytes: &[u8]) -> String {
+    format!("sha512-{}", STANDARD.encode(Sha512::digest(bytes)))
+}
+
+/// Upper bound on the pre-allocation in [`inflate_gzip`]. An SDF record is
+/// at most a few MB; this is generous headroom without trusting a remote
+/// ISIZE footer enough to let it force a multi-gigabyte allocation.
+const MAX_INFLATE_CAPACITY_HINT: usize = 256 * 1024 * 1024;
+
+/// Inflate a gzip-compressed payload, pre-sizing the output buffer from the
+/// footer's little-endian ISIZE field (the last 4 bytes) instead of growing
+/// it incrementally. The hint is clamped to [`MAX_INFLATE_CAPACITY_HINT`]
+/// since it comes from the untrusted PubChem response and a crafted value
+/// could otherwise claim close to 4 GiB.
+fn inflate_gzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    let isize_hint = bytes
+        .len()
+        .checked_sub(4)
+        .and_then(|offset| bytes.get(offset..))
+        .and_then(|tail| tail.try_into().ok())
+        .map(u32::from_le_bytes)
+        .unwrap_or(0) as usize;
+
+    let mut buffer = Vec::with_capacity(isize_hint.min(MAX_INFLATE_CAPACITY_HINT));
+    flate2::read::GzDecoder::new(bytes)
+        .read_to_end(&mut buffer)
+        .context("Couldn't inflate gzip-compressed SDF payload")?;
+    Ok(buffer)
+}
+
+/// For each Identification `constituent`, cross-check its CAS against
+/// PubChem's CAS list for the same molecular formula and download the
+/// matching compound's SDF record into `output_dir`. A digest manifest in
+/// `output_dir` lets a later run verify an existing SDF instead of
+/// re-downloading it.
+///
+/// Each `search_formula`/`get_cas` lookup against PubChem also consults the
+/// on-disk [`ContentCache`] first, keyed by formula/CID, so re-running
+/// against the same dossier doesn't re-query PubChem for lookups it already
+/// has an answer for. Pass `no_cache: true` (e.g. for a `--refresh` run) to
+/// bypass it.
+pub fn fetch_sdfs(constituents: &[Record], output_dir: &Path, no_cache: bool) -> Result<()> {
+    let manifest_path = output_dir.join(MANIFEST_FILE);
+    let mut manifest = Manifest::load(&manifest_path);
+
+    let client = reqwest::blocking::ClientBuilder::new()
+        .no_gzip()
+        .build()
+        .context("Couldn't build PubChem HTTP client")?;
+
+    let cache = (!no_cache)
+        .then(content_cache::default_cache_dir)
+        .flatten()
+        .and_then(
+            |dir| match ContentCache::new(dir, content_cache::DEFAULT_TTL_SECS) {
+                Ok(cache) => Some(cache),
+                Err(error) => {
+                    eprintln!("Couldn't open content cache: {error}");
+                    None
+                }
+            },
+        );
+
+    for constituent in constituents {
+        if constituent.formula.is_empty() || constituent.formula == "N/A" {
+            continue;
+        }
+
+        if let Err(error) =
+            fetch_one_sdf(&client, constituent, output_dir, &mut manifest, cache.as_ref())
+        {
+            eprintln!(
+                "Couldn't fetch PubChem SDF for CAS {}: {error}",
+                constituent.cas
+            );
+        }
+    }
+
+    manifest.save(&manifest_path)
+}
+
+/// `search_formula`, consulting `cache` (keyed by `formula`) before querying
+/// PubChem.
+fn cached_search_formula(cache: Option<&ContentCache>, formula: &str) -> Result<Vec<String>> {
+    let key = format!("pubchem-formula:{formula}");
+    if let Some(cids) = cache
+        .and_then(|cache| cache.get(&key))
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    {
+        return Ok(cids);
+    }
+
+    let cids = search_formula(formula).context("Couldn't search PubChem by molecular formula")?;
+    if let Some(cache) = cache {
+        if let Ok(payload) = serde_json::to_vec(&cids) {
+            if let Err(error) = cache.put(&key, &payload) {
+                eprintln!("Couldn't cache PubChem formula search for {formula}: {error}");
+            }
+        }
+    }
+    Ok(cids)
+}
+
+/// `get_cas`, consulting `cache` (keyed by `cid`) before querying PubChem.
+fn cached_get_cas(cache: Option<&ContentCache>, cid: isize) -> Option<String> {
+    let key = format!("pubchem-cas:{cid}");
+    if let Some(cas) = cache
+        .and_then(|cache| cache.get(&key))
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+    {
+        return Some(cas);
+    }
+
+    let cas = get_cas(cid).ok()?;
+    if let Some(cache) = cache {
+        if let Err(error) = cache.put(&key, cas.as_bytes()) {
+            eprintln!("Couldn't cache PubChem CAS lookup for CID {cid}: {error}");
+        }
+    }
+    Some(cas)
+}
+
+fn fetch_one_sdf(
+    client: &reqwest::blocking::Client,
+    constituent: &Record,
+    output_dir: &Path,
+    manifest: &mut Manifest,
+    cache: Option<&ContentCache>,
+) -> Result<()> {
+    let cids = cached_search_formula(cache, &constituent.formula)?;
+
+    let cid = cids
+        .iter()
+        .filter_map(|cid| cid.parse::<isize>().ok())
+        .find(|&cid| cached_get_cas(cache, cid).map(|cas| cas == constituent.cas).unwrap_or(false))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No PubChem compound for formula {} matches CAS {}",
+                constituent.formula,
+                constituent.cas
+            )
+        })?;
+
+    let sdf_path = output_dir.join(format!("{cid}.sdf"));
+
+    if let Some(entry) = manifest.find(&constituent.cas) {
+        if entry.cid == cid && entry.path == sdf_path {
+            if let Ok(existing) = fs::read(&sdf_path) {
+                if sha512_digest(&existing) == entry.digest {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    let response = client
+        .get(format!(
+            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/compound/cid/{cid}/SDF"
+        ))
+        .send()
+        .with_context(|| format!("Couldn't download SDF for CID {cid}"))?;
+
+    let is_gzip = response
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .map(|value| value.as_bytes() == b"gzip")
+        .unwrap_or(false);
+
+    let raw = response.bytes()?.to_vec();
+    let bytes = if is_gzip { inflate_gzip(&raw)? } else { raw };
+
+    fs::write(&sdf_path, &bytes)?;
+
+    manifest.upsert(ManifestEntry {
+        cas: constituent.cas.clone(),
+        cid,
+        path: sdf_path,
+        digest: sha512_digest(&bytes),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! HTTP service mode: exposes scraped ECHA dossiers as JSON over a small
+//! axum app instead of the one-shot CLI flow in `main`.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use axum::extract::{Path as AxumPath, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use scrapper::validate::is_valid_dossier_id;
+use scrapper::{EchaSite, Record, Section, Subsection};
+use serde_json::json;
+
+/// Shared app state: a cache of already-scraped sections keyed by
+/// `"<dossier id>:<section>"`, so concurrent requests for the same dossier
+/// reuse already-scraped data like a garden cache of pages.
+struct AppState {
+    cache: Mutex<HashMap<String, Vec<Record>>>,
+    cache_path: Option<PathBuf>,
+    no_cache: bool,
+}
+
+enum ApiError {
+    InvalidDossier(String),
+    /// The scrape task itself failed to run to completion: a panic while
+    /// extracting `section`'s markup, or the task being cancelled/aborted.
+    ScrapeFailed { section: Section, reason: String },
+    /// The scrape task completed normally but found no Identification
+    /// constituents — the one section every valid dossier is expected to
+    /// have, so an empty result there signals a scrape gone wrong rather
+    /// than a normal outcome. Composition subsections are routinely empty
+    /// (most dossiers have no "generated upon use" or "other" composition)
+    /// and are returned as `200 []` instead of this error.
+    EmptySection(Section),
+    UnknownSection(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match &self {
+            ApiError::InvalidDossier(id) => (
+                StatusCode::BAD_REQUEST,
+                format!("'{id}' is not a valid dossier id"),
+            ),
+            ApiError::ScrapeFailed { section, reason } => (
+                StatusCode::BAD_GATEWAY,
+                format!("Couldn't scrape the {section} section for this dossier: {reason}"),
+            ),
+            ApiError::EmptySection(section) => (
+                StatusCode::BAD_GATEWAY,
+                format!("ECHA returned no {section} constituents for this dossier"),
+            ),
+            ApiError::UnknownSection(section) => (
+                StatusCode::NOT_FOUND,
+                format!("Unknown section '{section}'"),
+            ),
+        };
+
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+/// Turn a failed [`tokio::task::JoinError`] into a readable reason: the
+/// panic payload's message if the task panicked, or the join error's own
+/// message if it was cancelled/aborted instead.
+fn join_error_reason(error: tokio::task::JoinError) -> String {
+    if error.is_panic() {
+        let payload = error.into_panic();
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "scrape task panicked".to_string())
+    } else {
+        error.to_string()
+    }
+}
+
+fn parse_section(segment: &str) -> Result<Section, ApiError> {
+    match segment {
+        "identification" => Ok(Section::Identification),
+        "boundary" => Ok(Section::Composition(Subsection::Boundary)),
+        "legal-entity" => Ok(Section::Composition(Subsection::LegalEntity)),
+        "generated" => Ok(Section::Composition(Subsection::Generated)),
+        "other" => Ok(Section::Composition(Subsection::Other)),
+        other => Err(ApiError::UnknownSection(other.to_string())),
+    }
+}
+
+async fn fetch_section(
+    state: Arc<AppState>,
+    dossier: String,
+    section: Section,
+) -> Result<Json<Vec<Record>>, ApiError> {
+    if !is_valid_dossier_id(&dossier) {
+        return Err(ApiError::InvalidDossier(dossier));
+    }
+
+    let cache_key = format!("{dossier}:{section:?}");
+    if let Some(cached) = state.cache.lock().unwrap().get(&cache_key) {
+        return Ok(Json(cached.clone()));
+    }
+
+    let cache_path = state.cache_path.clone();
+    let no_cache = state.no_cache;
+    let data = tokio::task::spawn_blocking(move || {
+        let url = format!(
+            "https://echa.europa.eu/registration-dossier/-/registered-dossier/{dossier}"
+        );
+        let mut echa = EchaSite::new(&url, cache_path.as_deref(), no_cache);
+        if no_cache {
+            echa.set_cache_ttl_days(0);
+        }
+        echa.get_constituents(section)
+    })
+    .await
+    .map_err(|error| ApiError::ScrapeFailed {
+        section,
+        reason: join_error_reason(error),
+    })?;
+
+    if data.is_empty() && section == Section::Identification {
+        return Err(ApiError::EmptySection(section));
+    }
+
+    state.cache.lock().unwrap().insert(cache_key, data.clone());
+    Ok(Json(data))
+}
+
+async fn get_identification(
+    State(state): State<Arc<AppState>>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<Json<Vec<Record>>, ApiError> {
+    fetch_section(state, id, Section::Identification).await
+}
+
+async fn get_section(
+    State(state): State<Arc<AppState>>,
+    AxumPath((id, section)): AxumPath<(String, String)>,
+) -> Result<Json<Vec<Record>>, ApiError> {
+    let section = parse_section(&section)?;
+    fetch_section(state, id, section).await
+}
+
+fn router(cache_path: Option<PathBuf>, no_cache: bool) -> Router {
+    let state = Arc::new(AppState {
+        cache: Mutex::new(HashMap::new()),
+        cache_path,
+        no_cache,
+    });
+
+    Router::new()
+        .route("/dossier/:id", get(get_identification))
+        .route("/dossier/:id/:section", get(get_section))
+        .with_state(state)
+}
+
+/// Run the HTTP service at `addr` until it's killed. Scraped sections are
+/// persisted to `cache_path` the same way the one-shot CLI flow does.
+pub async fn serve(addr: SocketAddr, cache_path: Option<PathBuf>, no_cache: bool) -> Result<()> {
+    let app = router(cache_path, no_cache);
+    println!("Listening on http://{addr}");
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{EchaData, Section};
+
+/// Persistent on-disk cache of scraped dossier sections, backed by SQLite.
+///
+/// Rows are keyed by `(dossier, section)` and store the serde-JSON of the
+/// scraped [`EchaData`] alongside the unix timestamp it was fetched at, so
+/// repeated runs against the same dossier can skip the network entirely.
+pub(crate) struct DossierCache {
+    conn: Connection,
+}
+
+impl DossierCache {
+    /// Open (creating if necessary) the cache database at `path`.
+    pub(crate) fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path).context("Couldn't open dossier cache database")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cache (
+                dossier TEXT NOT NULL,
+                section TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL,
+                PRIMARY KEY (dossier, section)
+            )",
+            [],
+        )
+        .context("Couldn't create cache table")?;
+
+        Ok(DossierCache { conn })
+    }
+
+    /// Look up a cached [`EchaData`] for `dossier`/`section`, ignoring rows
+    /// older than `max_age_secs` (every row, when `max_age_secs` is `0`).
+    /// Returns `None` on a miss, a stale row, or a corrupt payload.
+    pub(crate) fn get(&self, dossier: &str, section: Section, max_age_secs: u64) -> Option<EchaData> {
+        let row: rusqlite::Result<(String, i64)> = self.conn.query_row(
+            "SELECT payload, fetched_at FROM cache WHERE dossier = ?1 AND section = ?2",
+            params![dossier, section.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        );
+
+        let (payload, fetched_at) = row.ok()?;
+        if max_age_secs == 0 || now_secs().saturating_sub(fetched_at.max(0) as u64) > max_age_secs {
+            return None;
+        }
+
+        serde_json::from_str(&payload).ok()
+    }
+
+    /// Insert or replace the cached data for `dossier`/`section`.
+    pub(crate) fn put(&self, dossier: &str, section: Section, data: &EchaData) -> Result<()> {
+        let payload = serde_json::to_string(data).context("Couldn't serialize EchaData for cache")?;
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO cache (dossier, section, payload, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![dossier, section.to_string(), payload, now_secs() as i64],
+            )
+            .context("Couldn't write cache entry")?;
+
+        Ok(())
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
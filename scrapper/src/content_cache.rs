@@ -0,0 +1,91 @@
+//! Content-addressed on-disk cache for raw fetched bytes (scraped ECHA
+//! pages, PubChem lookups), mirroring the compiler-wrapper caching model: a
+//! hashed key maps to a stored artifact plus a small metadata header.
+//!
+//! Lives under `directories`' platform cache dir rather than alongside the
+//! user-facing TSV/cache.db output, since it's an implementation detail
+//! callers shouldn't need to clean up by hand.
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How long a cached page/lookup is trusted before it's treated as stale,
+/// used by both the [`EchaSite`](crate::EchaSite) page cache and the
+/// PubChem lookup cache.
+pub const DEFAULT_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheMeta {
+    fetched_at: u64,
+}
+
+/// The platform cache dir for `echars`'s content-addressed cache, e.g.
+/// `~/.cache/echars/content` on Linux, rather than alongside the
+/// user-facing TSV/cache.db output under `--output-dir`.
+pub fn default_cache_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "echars").map(|dirs| dirs.cache_dir().join("content"))
+}
+
+/// A content-addressed cache rooted at `dir`, whose entries are treated as
+/// stale once older than `ttl_secs`.
+pub struct ContentCache {
+    dir: PathBuf,
+    ttl_secs: u64,
+}
+
+impl ContentCache {
+    /// Open (creating if necessary) a content cache at `dir`.
+    pub fn new(dir: impl Into<PathBuf>, ttl_secs: u64) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).context("Couldn't create content cache directory")?;
+        Ok(ContentCache { dir, ttl_secs })
+    }
+
+    fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        let hashed = format!("{:x}", Sha256::digest(key.as_bytes()));
+        (
+            self.dir.join(format!("{hashed}.bin")),
+            self.dir.join(format!("{hashed}.json")),
+        )
+    }
+
+    /// Look up `key`, returning `None` on a miss or an entry older than the
+    /// cache's TTL.
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let (artifact_path, meta_path) = self.paths(key);
+        let meta: CacheMeta = serde_json::from_str(&fs::read_to_string(meta_path).ok()?).ok()?;
+
+        if now_secs().saturating_sub(meta.fetched_at) > self.ttl_secs {
+            return None;
+        }
+
+        fs::read(artifact_path).ok()
+    }
+
+    /// Store `bytes` under `key`, alongside a metadata header recording when
+    /// it was fetched.
+    pub fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let (artifact_path, meta_path) = self.paths(key);
+        fs::write(&artifact_path, bytes).context("Couldn't write cached artifact")?;
+
+        let meta = CacheMeta {
+            fetched_at: now_secs(),
+        };
+        fs::write(&meta_path, serde_json::to_string(&meta)?)
+            .context("Couldn't write cache metadata")?;
+
+        Ok(())
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,51 @@
+use super::{Extractor, ExtractorEntry};
+use crate::parsing::parse_composition;
+use crate::{EchaData, Section, Subsection};
+use anyhow::Result;
+use scraper::Html;
+
+/// Declares one `Extractor` for a Composition(s) subsection. All four share
+/// the same parsing logic, parameterized by which [`Subsection`] they filter
+/// panels down to.
+macro_rules! composition_extractor {
+    ($name:ident, $subsection:expr, $id:literal) => {
+        struct $name;
+
+        impl Extractor for $name {
+            fn section_id(&self) -> &'static str {
+                $id
+            }
+
+            fn section(&self) -> Section {
+                Section::Composition($subsection)
+            }
+
+            fn extract(&self, document: &Html) -> Result<EchaData> {
+                parse_composition(document, Section::Composition($subsection))
+            }
+        }
+
+        inventory::submit! { ExtractorEntry(&$name) }
+    };
+}
+
+composition_extractor!(
+    BoundaryExtractor,
+    Subsection::Boundary,
+    "Boundary Composition(s)"
+);
+composition_extractor!(
+    LegalEntityExtractor,
+    Subsection::LegalEntity,
+    "Legal Entity Composition(s)"
+);
+composition_extractor!(
+    GeneratedExtractor,
+    Subsection::Generated,
+    "Composition(s) generated upon use"
+);
+composition_extractor!(
+    OtherExtractor,
+    Subsection::Other,
+    "Other types of composition(s)"
+);
@@ -0,0 +1,23 @@
+use super::{Extractor, ExtractorEntry};
+use crate::parsing::parse_identification;
+use crate::{EchaData, Section};
+use anyhow::Result;
+use scraper::Html;
+
+struct IdentificationExtractor;
+
+impl Extractor for IdentificationExtractor {
+    fn section_id(&self) -> &'static str {
+        "Identification"
+    }
+
+    fn section(&self) -> Section {
+        Section::Identification
+    }
+
+    fn extract(&self, document: &Html) -> Result<EchaData> {
+        parse_identification(document)
+    }
+}
+
+inventory::submit! { ExtractorEntry(&IdentificationExtractor) }
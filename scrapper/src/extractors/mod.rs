@@ -0,0 +1,42 @@
+//! Pluggable section extractors.
+//!
+//! Each ECHA section (Identification, the Composition(s) subsections, and
+//! any future ones) is scraped by a self-contained [`Extractor`] that
+//! registers itself with `inventory::submit!`. [`EchaSite::get_constituents`]
+//! looks the section up in this registry instead of matching on it directly,
+//! so adding a new section is a matter of adding one new file here rather
+//! than editing a central dispatcher.
+
+mod composition;
+mod identification;
+
+use crate::{EchaData, Section};
+use anyhow::Result;
+use scraper::Html;
+
+/// A self-contained scraper for one dossier section.
+pub trait Extractor: Sync {
+    /// Stable identifier for this section, matching the panel/heading text
+    /// ECHA uses for it (or `"Identification"` for the top-level block).
+    fn section_id(&self) -> &'static str;
+
+    /// The [`Section`] this extractor produces data for.
+    fn section(&self) -> Section;
+
+    /// Scrape this extractor's section out of a parsed dossier page.
+    fn extract(&self, document: &Html) -> Result<EchaData>;
+}
+
+/// One registered [`Extractor`], submitted via `inventory::submit!` so
+/// extractor modules self-register at startup.
+pub struct ExtractorEntry(pub &'static dyn Extractor);
+
+inventory::collect!(ExtractorEntry);
+
+/// Look up the registered extractor for `section`, if any.
+pub(crate) fn find(section: Section) -> Option<&'static dyn Extractor> {
+    inventory::iter::<ExtractorEntry>
+        .into_iter()
+        .map(|entry| entry.0)
+        .find(|extractor| extractor.section() == section)
+}
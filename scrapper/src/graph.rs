@@ -0,0 +1,109 @@
+//! Opt-in crawl mode that follows each constituent's `weblink` to its own
+//! dossier, building a deduplicated graph of which substances reference
+//! which instead of scraping a single, isolated dossier.
+
+use crate::validate::parse_dossier_url;
+use crate::{dossier_id_from_url, EchaSite, Record, ALL_SECTIONS};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+/// Every constituent scraped during a [`crawl`], plus an adjacency list of
+/// which dossier (by id) references which.
+pub struct CrawlResult {
+    pub records: Vec<Record>,
+    pub adjacency: HashMap<String, Vec<String>>,
+}
+
+/// Fetch and scrape every standard section of a single dossier, returning
+/// its records and the (id, canonical url) of every `weblink` among them
+/// that actually resolves to another ECHA dossier.
+///
+/// [`EchaSite::get_constituents`] panics on a page that doesn't match the
+/// expected markup, which [`crawl`] isolates per node via `catch_unwind`
+/// rather than letting it take down the whole BFS, so this is a plain
+/// function rather than something that reports failure itself.
+fn scrape_node(
+    url: &str,
+    cache_path: Option<&Path>,
+    no_cache: bool,
+) -> (Vec<Record>, Vec<(String, String)>) {
+    let mut echa = EchaSite::new(url, cache_path, no_cache);
+    if no_cache {
+        echa.set_cache_ttl_days(0);
+    }
+
+    let mut records = Vec::new();
+    let mut references = Vec::new();
+
+    for section in ALL_SECTIONS {
+        let data = echa.get_constituents(section);
+        for record in &data {
+            if let Ok(dossier_ref) = parse_dossier_url(&record.weblink) {
+                references.push((dossier_ref.id, dossier_ref.canonical_url));
+            }
+        }
+        records.extend(data);
+    }
+
+    (records, references)
+}
+
+/// Crawl outward from `start_url`, following each constituent's `weblink` to
+/// its own dossier and scraping it the same way, up to `max_depth` hops away.
+/// A visited-set keyed by dossier id prevents cycles and duplicate work.
+///
+/// Each discovered `weblink` is validated with [`parse_dossier_url`] before
+/// it's enqueued, so the crawl never follows an off-site link or wanders
+/// outside echa.europa.eu. A single dossier that fails to scrape (a network
+/// error, or a page whose markup makes an [`Extractor`](crate::extractors::Extractor)
+/// panic) is logged and skipped rather than aborting the whole crawl and
+/// discarding every record already collected.
+pub fn crawl(
+    start_url: &str,
+    cache_path: Option<PathBuf>,
+    max_depth: usize,
+    no_cache: bool,
+) -> Result<CrawlResult> {
+    let mut records = Vec::new();
+    let mut adjacency: HashMap<String, Vec<String>> = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut queue: VecDeque<(String, usize)> = VecDeque::new();
+    queue.push_back((start_url.to_string(), 0));
+
+    while let Some((url, depth)) = queue.pop_front() {
+        let Some(dossier_id) = dossier_id_from_url(&url) else {
+            continue;
+        };
+        if !visited.insert(dossier_id.clone()) {
+            continue;
+        }
+
+        let cache_path = cache_path.as_deref();
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            scrape_node(&url, cache_path, no_cache)
+        }));
+
+        let (data, references) = match outcome {
+            Ok(result) => result,
+            Err(_) => {
+                eprintln!("Couldn't scrape dossier {dossier_id}, skipping it and continuing the crawl");
+                continue;
+            }
+        };
+
+        let mut edges = Vec::with_capacity(references.len());
+        for (reference_id, reference_url) in references {
+            edges.push(reference_id.clone());
+            if depth < max_depth && !visited.contains(&reference_id) {
+                queue.push_back((reference_url, depth + 1));
+            }
+        }
+
+        records.extend(data);
+        adjacency.insert(dossier_id, edges);
+    }
+
+    Ok(CrawlResult { records, adjacency })
+}
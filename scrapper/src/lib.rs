@@ -1,15 +1,30 @@
-use anyhow::{anyhow, Error, Result};
+mod cache;
+pub mod content_cache;
+pub mod extractors;
+pub mod graph;
+mod parsing;
+pub mod store;
+pub mod validate;
+
+use anyhow::{anyhow, Context, Error, Result};
+use cache::DossierCache;
+use content_cache::ContentCache;
+use extractors::Extractor;
 use reqwest::blocking::Client;
 use reqwest::ClientBuilder;
-use scraper::{ElementRef, Html, Selector};
+use scraper::Html;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
-use std::ptr::write;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::{Duration, Instant};
 
+/// Default number of days a cached section is considered fresh before
+/// [`EchaSite::get_constituents`] treats it as stale and re-scrapes.
+const DEFAULT_CACHE_TTL_DAYS: u64 = 30;
+
 type EchaData = Vec<Record>;
 
 // idcoordinates2D	FragFp	EC	Weblink	Structure	Section	Image	Subsection	Name	Reference Substance	Constitute	Reference EC	Reference CAS
@@ -21,7 +36,7 @@ pub struct Record {
     id: String,
     #[serde(alias = "Weblink")]
     pub weblink: String,
-    #[serde(skip_serializing)]
+    #[serde(skip_serializing, default)]
     structure: String,
     #[serde(alias = "Section")]
     section: String,
@@ -123,15 +138,31 @@ pub enum Section {
 }
 
 /// Represents and manages the data for each section and subsection of the provided url.
-#[derive(Debug)]
 pub struct EchaSite<'a> {
     url: Cow<'a, str>,
     data: HashMap<Section, EchaData>,
     document: Result<Html>,
+    cache: Option<DossierCache>,
+    cache_ttl_secs: u64,
+}
+
+/// Extract the dossier number from an ECHA registered-dossier url, e.g.
+/// `.../registered-dossier/24529` -> `"24529"`.
+pub(crate) fn dossier_id_from_url(url: &str) -> Option<String> {
+    url.trim_end_matches('/').split('/').last().map(String::from)
 }
 
-/// Fetch the html body of the provided url.
-fn fetch_document(url: &str) -> Result<Html> {
+/// Fetch the html body of the provided url, consulting `content_cache`
+/// first so re-scraping the same dossier during development doesn't hammer
+/// echa.europa.eu for an unchanged page.
+fn fetch_document(url: &str, content_cache: Option<&ContentCache>) -> Result<Html> {
+    let cache_key = format!("echa-page:{url}");
+    if let Some(cache) = content_cache {
+        if let Some(body) = cache.get(&cache_key).and_then(|bytes| String::from_utf8(bytes).ok()) {
+            return Ok(Html::parse_document(&body));
+        }
+    }
+
     let client = reqwest::blocking::ClientBuilder::new()
         .connection_verbose(true)
         .timeout(Duration::from_secs(120))
@@ -142,218 +173,141 @@ fn fetch_document(url: &str) -> Result<Html> {
     let elapsed = now.elapsed().as_secs();
     println!("\tFetched in: {} seconds", elapsed);
 
+    if let Some(cache) = content_cache {
+        if let Err(error) = cache.put(&cache_key, body.as_bytes()) {
+            eprintln!("Couldn't cache fetched page for {url}: {error}");
+        }
+    }
+
     Ok(Html::parse_document(&body))
 }
 
-/// Scrap the data of each constituent from the provided section.
-/// <br>
-/// The result is a vector where each element represents a panel from a section/subsection as a vector.
-/// Each element of the latter is a HashMap with the data of the constituent.
+/// Scrape the data of each constituent from the provided section by
+/// dispatching to whichever [`Extractor`] is registered for it.
 fn data_from(document: &Html, section: Section) -> Result<EchaData> {
-    // **************************************************
-    //*** Closure to obtain data from a sBlock
-    let obtain_data = |data_html: ElementRef| -> Result<HashMap<String, String>> {
-        // Useful selectors
-        let dt_selector = Selector::parse("dt").unwrap();
-        let dd_selector = Selector::parse("dd").unwrap();
-        let img_selector = Selector::parse("img").unwrap();
-        let constituent = Selector::parse("h5").unwrap();
-
-        let key_names = data_html // Get key names
-            .select(&dt_selector)
-            .flat_map(|data| data.text())
-            .map(|key| key.replace(":", ""));
-
-        let key_values = data_html // Get key values
-            .select(&dd_selector)
-            .map(|data| {
-                data.text()
-                    .collect::<String>()
-                    .trim()
-                    .replace("\n", "")
-                    .replace("\t", "")
-            });
+    extractors::find(section)
+        .ok_or_else(|| anyhow!("No extractor registered for section: {section:?}"))?
+        .extract(document)
+}
 
-        let img = data_html.select(&img_selector).map(|link| {
-            // Get image link if exists
-            (
-                String::from("Image link"),
-                String::from(link.value().attr("src").unwrap_or("")),
-            )
+impl<'a> EchaSite<'a> {
+    /// Create a new instance of the structure and fetch the html body
+    /// from the provided url using [`fetch_document`].
+    ///
+    /// When `cache_path` is provided, scraped sections are persisted to (and
+    /// looked up from) a SQLite database at that path, keyed by the dossier
+    /// number parsed from `url` and the requested [`Section`]. Pass `None` to
+    /// disable persistent caching.
+    ///
+    /// When `no_cache` is `false`, the raw html fetched for `url` is also
+    /// looked up in (and saved to) the on-disk [`ContentCache`] under
+    /// [`content_cache::default_cache_dir`], so re-scraping an unchanged
+    /// dossier skips the network entirely. Pass `no_cache: true` to bypass
+    /// it, e.g. for a `--refresh` run.
+    pub fn new(url: &'a str, cache_path: Option<&Path>, no_cache: bool) -> Self {
+        let cache = cache_path.and_then(|path| match DossierCache::open(path) {
+            Ok(cache) => Some(cache),
+            Err(error) => {
+                eprintln!("Couldn't open dossier cache at {}: {error}", path.display());
+                None
+            }
         });
 
-        let consti_num = data_html
-            .select(&constituent)
-            .flat_map(|consti| consti.text())
-            .map(|consti| {
-                (
-                    String::from("Constituent"),
-                    consti.to_string(),
-                    // String::from(consti.split(' ').last().unwrap()),
-                )
+        let content_cache = (!no_cache)
+            .then(content_cache::default_cache_dir)
+            .flatten()
+            .and_then(|dir| match ContentCache::new(dir, content_cache::DEFAULT_TTL_SECS) {
+                Ok(cache) => Some(cache),
+                Err(error) => {
+                    eprintln!("Couldn't open content cache: {error}");
+                    None
+                }
             });
 
-        Ok(key_names // Merge key names with key values
-            .zip(key_values)
-            .chain(img)
-            .chain(consti_num)
-            .collect())
-    };
-
-    // **************************************************
-    //*** Closure to get data from Identification section
-    let id_data = || -> Result<EchaData> {
-        let id_selector = Selector::parse("#sIdentification + div.sBlock").unwrap();
-        let id_html = document // Get html info
-            .select(&id_selector)
-            .next()
-            .expect("Problem obtaining identification html");
-
-        let wrap = obtain_data(id_html).expect("Couldn't obtain Identification data");
-
-        Ok(vec![Record {
-            idcoordinates2D: "N/A".to_string(),
-            FragFp: "N/A".to_string(),
-            id: "N/A".to_string(),
-            weblink: "N/A".to_string(),
-            structure: "N/A".to_string(),
-            section: "Identification".to_string(),
-            image: wrap.get("Image link").unwrap_or(&"N/A".to_string()).clone(),
-            subsection: "N/A".to_string(),
-            name: wrap.get("Display Name").unwrap_or(&"N/A".to_string()).clone(),
-            substance: wrap.get("Display Name").unwrap_or(&"N/A".to_string()).clone(),
-            constitute: wrap
-                .get("Constituent")
-                .unwrap_or(&"N/A".to_string())
-                .clone(),
-            ec: wrap.get("EC Number").unwrap_or(&"N/A".to_string()).clone(),
-            cas: wrap.get("CAS Number").unwrap_or(&"N/A".to_string()).clone(),
-            formula: wrap
-                .get("Molecular formula")
-                .unwrap_or(&"N/A".to_string())
-                .clone(),
-            pubchem_cas: "".to_string()
-        }])
-    };
-
-    // **************************************************
-    //*** Get subsection and panels data
-    let panels_selector = Selector::parse("div.panel-group > h4 ,div.panel.panel-default")
-        .expect("panels_selector not created");
-    let block_selector = Selector::parse("div.sBlock").expect("block_selector not created");
-    let title_selector = Selector::parse("h4.panel-title").expect("title_selector not created");
+        EchaSite {
+            url: Cow::from(url),
+            data: HashMap::default(),
+            document: fetch_document(&url, content_cache.as_ref()),
+            cache,
+            cache_ttl_secs: DEFAULT_CACHE_TTL_DAYS * 24 * 60 * 60,
+        }
+    }
 
-    // **************************************************
-    //*** Closure to get data from Compositions section
-    let compositions_data = |subsection_enum| -> Result<EchaData> {
-        // Sort each panel to know which subsection it belongs to. Returns an iterator containing tuples (Subsection, Node)
-        // Each panel has x constituents
-        // h4 headers -> Subsections and the title of each listing item
-        let sorted_panels_data = document
-            .select(&panels_selector)
-            .scan(Section::Composition(Subsection::Other), |state, node| {
-                let kind = node.value().name();
+    /// Override how long (in days) a cached section is trusted before it's
+    /// considered stale and re-scraped. Passing `0` forces every lookup to
+    /// miss, which is how a `--refresh` flag can bypass the cache.
+    pub fn set_cache_ttl_days(&mut self, days: u64) {
+        self.cache_ttl_secs = days * 24 * 60 * 60;
+    }
 
-                if kind == "h4" {
-                    let subsection = node
-                        .text()
-                        .map(|e| e.trim().replace("\n", "").replace("\t", ""))
-                        .collect::<String>()
-                        .replace("open allclose all", "");
+    /// Returns the information of each constituent of the [`Section`] provided as an [`EchaData`] type.
+    pub fn get_constituents(&mut self, section: Section) -> EchaData {
+        if let Some(data) = self.data.get(&section) {
+            return data.clone();
+        }
 
-                    *state = Section::Composition(Subsection::from_str(subsection.as_str()).ok()?);
-                }
+        let dossier = dossier_id_from_url(&self.url);
 
-                Some((*state, node))
-            })
-            .filter(|(_, node)| node.value().name() != "h4");
+        if let (Some(cache), Some(dossier)) = (&self.cache, dossier.as_deref()) {
+            if let Some(cached) = cache.get(dossier, section, self.cache_ttl_secs) {
+                self.data.insert(section, cached.clone());
+                return cached;
+            }
+        }
 
-        // Obtain constituents data of current panel
-        let constituent_data: EchaData = sorted_panels_data
-            .filter(|(subsection, _)| *subsection == subsection_enum)
-            // .inspect(|x| println!("Constituent {:?} {:?}", x.0, x.1.value().name()))
-            .map(|(subsection, node)| {
-                // Get the current panel title
-                let panel_title: String = node
-                    .select(&title_selector)
-                    .flat_map(|e| e.text())
-                    .map(|title| title.trim())
-                    .filter(|title| !title.is_empty())
-                    // .inspect(|t| eprintln!("t = {:#?}", t))
-                    .collect();
+        let document = match &self.document {
+            Ok(doc) => doc,
+            Err(error) => panic!("Couldn't obtain html body {error:?}"),
+        };
 
-                node.select(&block_selector)
-                    .map(|constituent| obtain_data(constituent).unwrap())
-                    .map(|mut data| {
-                        data.insert("Name".to_string(), panel_title.to_string());
-                        data
-                    })
-                    .map(|wrap| Record {
-                        idcoordinates2D: "N/A".to_string(),
-                        FragFp: "N/A".to_string(),
-                        id: "N/A".to_string(),
-                        weblink: "N/A".to_string(),
-                        structure: "N/A".to_string(),
-                        section: "Composition(s)".to_string(),
-                        image: wrap.get("Image link").unwrap_or(&"N/A".to_string()).clone(),
-                        subsection: subsection.to_string(),
-                        name: wrap.get("Name").unwrap_or(&"N/A".to_string()).clone(),
-                        substance: wrap
-                            .get("Reference substance name")
-                            .unwrap_or(&"N/A".to_string())
-                            .clone(),
-                        constitute: wrap
-                            .get("Constituent")
-                            .unwrap_or(&"N/A".to_string())
-                            .clone(),
-                        ec: wrap.get("EC Number").unwrap_or(&"N/A".to_string()).clone(),
-                        cas: wrap.get("CAS Number").unwrap_or(&"N/A".to_string()).clone(),
-                        formula: wrap
-                            .get("Molecular formula")
-                            .unwrap_or(&"N/A".to_string())
-                            .clone(),
-                        pubchem_cas: "".to_string()
-                    })
-                    .collect::<EchaData>()
-                // .collect::<Vec<HashMap<String, String>>>()
-            })
-            .flatten()
-            .collect();
+        let new_data = data_from(document, section).unwrap();
 
-        Ok(constituent_data)
-    };
+        if let (Some(cache), Some(dossier)) = (&self.cache, dossier.as_deref()) {
+            if let Err(error) = cache.put(dossier, section, &new_data) {
+                eprintln!("Couldn't persist cache entry for dossier {dossier}: {error}");
+            }
+        }
 
-    match section {
-        Section::Identification => id_data(),
-        Section::Composition(sub) => compositions_data(Section::Composition(sub)),
+        self.data.insert(section, new_data.clone());
+        new_data
     }
 }
 
-impl<'a> EchaSite<'a> {
-    /// Create a new instance of the structure and fetch the html body
-    /// from the provided url using [`fetch_document`].
-    pub fn new(url: &'a str) -> Self {
-        EchaSite {
-            url: Cow::from(url),
-            data: HashMap::default(),
-            document: fetch_document(&url),
-        }
-    }
+/// The standard sections every dossier is scraped for by [`scrape_all`] and
+/// the one-shot CLI flow.
+pub const ALL_SECTIONS: [Section; 5] = [
+    Section::Identification,
+    Section::Composition(Subsection::Boundary),
+    Section::Composition(Subsection::LegalEntity),
+    Section::Composition(Subsection::Generated),
+    Section::Composition(Subsection::Other),
+];
 
-    /// Returns the information of each constituent of the [`Section`] provided as an [`EchaData`] type.
-    pub fn get_constituents(&mut self, section: Section) -> EchaData {
-        match self.data.get(&section) {
-            Some(data) => data.clone(),
-            None => {
-                let document = match &self.document {
-                    Ok(doc) => doc,
-                    Err(error) => panic!("Couldn't obtain html body {error:?}"),
-                };
-
-                let new_data = data_from(document, section).unwrap();
-                self.data.insert(section, new_data.clone());
-                new_data
-            }
+/// Scrape every standard section of a single dossier on a blocking thread.
+///
+/// [`EchaSite`] is built on the blocking `reqwest` client, so driving many
+/// dossiers concurrently from an async batch runner means offloading each
+/// one via [`tokio::task::spawn_blocking`] rather than making the scraper
+/// itself async.
+///
+/// `no_cache` bypasses both the on-disk [`content_cache::ContentCache`] (via
+/// [`EchaSite::new`]) and the persistent dossier cache's TTL (by zeroing it),
+/// so a `--refresh` run never serves stale parsed sections.
+pub async fn scrape_all(
+    url: String,
+    cache_path: Option<PathBuf>,
+    no_cache: bool,
+) -> Result<HashMap<Section, EchaData>> {
+    tokio::task::spawn_blocking(move || {
+        let mut echa = EchaSite::new(&url, cache_path.as_deref(), no_cache);
+        if no_cache {
+            echa.set_cache_ttl_days(0);
         }
-    }
+        ALL_SECTIONS
+            .iter()
+            .map(|&section| (section, echa.get_constituents(section)))
+            .collect()
+    })
+    .await
+    .context("Dossier scrape task panicked")
 }
@@ -0,0 +1,179 @@
+//! Shared HTML-scraping helpers used by the [`crate::extractors`] that parse
+//! a dossier's sections out of the page `scraper::Html`.
+
+use crate::{EchaData, Record, Section, Subsection};
+use anyhow::Result;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Pull the dt/dd key-value pairs (plus any image link / constituent
+/// number) out of a single `div.sBlock` panel.
+pub(crate) fn obtain_data(data_html: ElementRef) -> Result<HashMap<String, String>> {
+    // Useful selectors
+    let dt_selector = Selector::parse("dt").unwrap();
+    let dd_selector = Selector::parse("dd").unwrap();
+    let img_selector = Selector::parse("img").unwrap();
+    let constituent = Selector::parse("h5").unwrap();
+
+    let key_names = data_html // Get key names
+        .select(&dt_selector)
+        .flat_map(|data| data.text())
+        .map(|key| key.replace(":", ""));
+
+    let key_values = data_html // Get key values
+        .select(&dd_selector)
+        .map(|data| {
+            data.text()
+                .collect::<String>()
+                .trim()
+                .replace("\n", "")
+                .replace("\t", "")
+        });
+
+    let img = data_html.select(&img_selector).map(|link| {
+        // Get image link if exists
+        (
+            String::from("Image link"),
+            String::from(link.value().attr("src").unwrap_or("")),
+        )
+    });
+
+    let consti_num = data_html
+        .select(&constituent)
+        .flat_map(|consti| consti.text())
+        .map(|consti| (String::from("Constituent"), consti.to_string()));
+
+    let weblink_selector = Selector::parse("a[href]").unwrap();
+    let weblink = data_html.select(&weblink_selector).map(|link| {
+        // Link to the reference substance's own dossier, if this panel has one
+        (
+            String::from("Weblink"),
+            String::from(link.value().attr("href").unwrap_or("")),
+        )
+    });
+
+    Ok(key_names // Merge key names with key values
+        .zip(key_values)
+        .chain(img)
+        .chain(consti_num)
+        .chain(weblink)
+        .collect())
+}
+
+/// Scrape the top-level Identification block of a dossier page.
+pub(crate) fn parse_identification(document: &Html) -> Result<EchaData> {
+    let id_selector = Selector::parse("#sIdentification + div.sBlock").unwrap();
+    let id_html = document
+        .select(&id_selector)
+        .next()
+        .expect("Problem obtaining identification html");
+
+    let wrap = obtain_data(id_html).expect("Couldn't obtain Identification data");
+
+    Ok(vec![Record {
+        idcoordinates2D: "N/A".to_string(),
+        FragFp: "N/A".to_string(),
+        id: "N/A".to_string(),
+        weblink: "N/A".to_string(),
+        structure: "N/A".to_string(),
+        section: "Identification".to_string(),
+        image: wrap.get("Image link").unwrap_or(&"N/A".to_string()).clone(),
+        subsection: "N/A".to_string(),
+        name: wrap.get("Display Name").unwrap_or(&"N/A".to_string()).clone(),
+        substance: wrap.get("Display Name").unwrap_or(&"N/A".to_string()).clone(),
+        constitute: wrap
+            .get("Constituent")
+            .unwrap_or(&"N/A".to_string())
+            .clone(),
+        ec: wrap.get("EC Number").unwrap_or(&"N/A".to_string()).clone(),
+        cas: wrap.get("CAS Number").unwrap_or(&"N/A".to_string()).clone(),
+        formula: wrap
+            .get("Molecular formula")
+            .unwrap_or(&"N/A".to_string())
+            .clone(),
+        pubchem_cas: "".to_string(),
+    }])
+}
+
+/// Scrape one Composition(s) subsection (Boundary / Legal Entity / Generated
+/// / Other) of a dossier page.
+pub(crate) fn parse_composition(document: &Html, subsection_enum: Section) -> Result<EchaData> {
+    let panels_selector = Selector::parse("div.panel-group > h4 ,div.panel.panel-default")
+        .expect("panels_selector not created");
+    let block_selector = Selector::parse("div.sBlock").expect("block_selector not created");
+    let title_selector = Selector::parse("h4.panel-title").expect("title_selector not created");
+
+    // Sort each panel to know which subsection it belongs to. Returns an iterator containing tuples (Subsection, Node)
+    // Each panel has x constituents
+    // h4 headers -> Subsections and the title of each listing item
+    let sorted_panels_data = document
+        .select(&panels_selector)
+        .scan(Section::Composition(Subsection::Other), |state, node| {
+            let kind = node.value().name();
+
+            if kind == "h4" {
+                let subsection = node
+                    .text()
+                    .map(|e| e.trim().replace("\n", "").replace("\t", ""))
+                    .collect::<String>()
+                    .replace("open allclose all", "");
+
+                *state = Section::Composition(Subsection::from_str(subsection.as_str()).ok()?);
+            }
+
+            Some((*state, node))
+        })
+        .filter(|(_, node)| node.value().name() != "h4");
+
+    // Obtain constituents data of current panel
+    let constituent_data: EchaData = sorted_panels_data
+        .filter(|(subsection, _)| *subsection == subsection_enum)
+        .map(|(subsection, node)| {
+            // Get the current panel title
+            let panel_title: String = node
+                .select(&title_selector)
+                .flat_map(|e| e.text())
+                .map(|title| title.trim())
+                .filter(|title| !title.is_empty())
+                .collect();
+
+            node.select(&block_selector)
+                .map(|constituent| obtain_data(constituent).unwrap())
+                .map(|mut data| {
+                    data.insert("Name".to_string(), panel_title.to_string());
+                    data
+                })
+                .map(|wrap| Record {
+                    idcoordinates2D: "N/A".to_string(),
+                    FragFp: "N/A".to_string(),
+                    id: "N/A".to_string(),
+                    weblink: wrap.get("Weblink").unwrap_or(&"N/A".to_string()).clone(),
+                    structure: "N/A".to_string(),
+                    section: "Composition(s)".to_string(),
+                    image: wrap.get("Image link").unwrap_or(&"N/A".to_string()).clone(),
+                    subsection: subsection.to_string(),
+                    name: wrap.get("Name").unwrap_or(&"N/A".to_string()).clone(),
+                    substance: wrap
+                        .get("Reference substance name")
+                        .unwrap_or(&"N/A".to_string())
+                        .clone(),
+                    constitute: wrap
+                        .get("Constituent")
+                        .unwrap_or(&"N/A".to_string())
+                        .clone(),
+                    ec: wrap.get("EC Number").unwrap_or(&"N/A".to_string()).clone(),
+                    cas: wrap.get("CAS Number").unwrap_or(&"N/A".to_string()).clone(),
+                    formula: wrap
+                        .get("Molecular formula")
+                        .unwrap_or(&"N/A".to_string())
+                        .clone(),
+                    pubchem_cas: "".to_string(),
+                })
+                .collect::<EchaData>()
+        })
+        .flatten()
+        .collect();
+
+    Ok(constituent_data)
+}
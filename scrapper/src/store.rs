@@ -0,0 +1,143 @@
+//! Persistent, queryable SQLite index of every constituent ever scraped or
+//! imported, keyed by dossier id.
+//!
+//! Distinct from [`crate::cache`]'s `DossierCache`, which only caches parsed
+//! sections for a limited time to speed up re-scraping the same dossier: the [`Store`]
+//! accumulates constituents across however many dossiers a user has ever
+//! scraped or bulk-imported, so cross-dossier questions like "every
+//! substance with CAS X" can be answered without re-scraping anything.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+use crate::Record;
+
+/// One row out of the store: a scraped constituent plus the dossier it came
+/// from.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StoredRecord {
+    pub dossier: String,
+    pub section: String,
+    pub subsection: String,
+    pub name: String,
+    pub cas: String,
+    pub ec: String,
+    pub formula: String,
+    pub substance: String,
+    pub weblink: String,
+}
+
+/// Filters for [`Store::query`]; a `None` field matches anything.
+#[derive(Debug, Default, Clone)]
+pub struct QueryFilter {
+    pub cas: Option<String>,
+    pub formula: Option<String>,
+    pub section: Option<String>,
+}
+
+/// A persistent SQLite index of scraped constituents, accumulated across
+/// however many dossiers have been scraped or imported into it.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open (creating if necessary) the store database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path).context("Couldn't open dossier store database")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS constituents (
+                dossier TEXT NOT NULL,
+                section TEXT NOT NULL,
+                subsection TEXT NOT NULL,
+                name TEXT NOT NULL,
+                cas TEXT NOT NULL,
+                ec TEXT NOT NULL,
+                formula TEXT NOT NULL,
+                substance TEXT NOT NULL,
+                weblink TEXT NOT NULL,
+                PRIMARY KEY (dossier, section, subsection, cas, name)
+            )",
+            [],
+        )
+        .context("Couldn't create constituents table")?;
+
+        Ok(Store { conn })
+    }
+
+    /// Insert or replace every one of `records` under `dossier_id`, in a
+    /// single transaction. Returns how many were written.
+    pub fn insert_all(&mut self, dossier_id: &str, records: &[Record]) -> Result<usize> {
+        let tx = self
+            .conn
+            .transaction()
+            .context("Couldn't start store transaction")?;
+
+        for record in records {
+            tx.execute(
+                "INSERT OR REPLACE INTO constituents
+                    (dossier, section, subsection, name, cas, ec, formula, substance, weblink)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    dossier_id,
+                    record.section,
+                    record.subsection,
+                    record.name,
+                    record.cas,
+                    record.ec,
+                    record.formula,
+                    record.substance,
+                    record.weblink,
+                ],
+            )
+            .context("Couldn't insert constituent")?;
+        }
+
+        tx.commit().context("Couldn't commit store transaction")?;
+        Ok(records.len())
+    }
+
+    /// Filter stored constituents by `filter`, matching exactly on whichever
+    /// of its fields are `Some`.
+    pub fn query(&self, filter: &QueryFilter) -> Result<Vec<StoredRecord>> {
+        let mut sql = String::from(
+            "SELECT dossier, section, subsection, name, cas, ec, formula, substance, weblink
+             FROM constituents WHERE 1 = 1",
+        );
+        let mut values: Vec<&dyn rusqlite::ToSql> = Vec::new();
+
+        if let Some(cas) = &filter.cas {
+            sql.push_str(" AND cas = ?");
+            values.push(cas);
+        }
+        if let Some(formula) = &filter.formula {
+            sql.push_str(" AND formula = ?");
+            values.push(formula);
+        }
+        if let Some(section) = &filter.section {
+            sql.push_str(" AND section = ?");
+            values.push(section);
+        }
+
+        let mut stmt = self.conn.prepare(&sql).context("Couldn't prepare store query")?;
+        let rows = stmt
+            .query_map(values.as_slice(), |row| {
+                Ok(StoredRecord {
+                    dossier: row.get(0)?,
+                    section: row.get(1)?,
+                    subsection: row.get(2)?,
+                    name: row.get(3)?,
+                    cas: row.get(4)?,
+                    ec: row.get(5)?,
+                    formula: row.get(6)?,
+                    substance: row.get(7)?,
+                    weblink: row.get(8)?,
+                })
+            })
+            .context("Couldn't run store query")?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("Couldn't read store query results")
+    }
+}
@@ -0,0 +1,195 @@
+//! Input validation for ECHA dossier URLs.
+//!
+//! `main` used to pull the dossier number out of a url with
+//! `url.split('/').last()`, which silently produces garbage for a url with a
+//! trailing slash, query string, or fragment, and never checked that the
+//! host was actually an ECHA domain. [`parse_dossier_url`] replaces that with
+//! a real parse, so callers get a clear error instead of a truncated output
+//! filename.
+
+use anyhow::{anyhow, Result};
+use url::Url;
+
+const ECHA_HOST: &str = "echa.europa.eu";
+
+/// A validated reference to an ECHA registered-dossier page: its numeric id
+/// and the canonical url rebuilt from that id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DossierRef {
+    pub id: String,
+    pub canonical_url: String,
+}
+
+/// Parse `input` as an ECHA registered-dossier url.
+///
+/// Confirms the host resolves to [`ECHA_HOST`] (applying IDNA normalization
+/// via `domain_to_ascii` so punycode/mixed-case hosts are accepted), walks
+/// the path segments to reliably locate the `registered-dossier/<id>`
+/// component, and returns a [`DossierRef`] with a normalized url.
+pub fn parse_dossier_url(input: &str) -> Result<DossierRef> {
+    let url = Url::parse(input).map_err(|error| anyhow!("'{input}' is not a valid url: {error}"))?;
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("'{input}' has no host"))?;
+    let ascii_host = idna::domain_to_ascii(host)
+        .map_err(|error| anyhow!("'{input}' has an invalid host: {error:?}"))?;
+
+    if ascii_host != ECHA_HOST {
+        return Err(anyhow!(
+            "'{input}' does not point at {ECHA_HOST} (got '{ascii_host}')"
+        ));
+    }
+
+    let id = url
+        .path_segments()
+        .and_then(|segments| {
+            let segments: Vec<&str> = segments.collect();
+            segments
+                .iter()
+                .position(|&segment| segment == "registered-dossier")
+                .and_then(|index| segments.get(index + 1))
+                .map(|segment| segment.to_string())
+        })
+        .filter(|id| is_valid_dossier_id(id))
+        .ok_or_else(|| anyhow!("Couldn't find a registered-dossier id in '{input}'"))?;
+
+    Ok(dossier_ref_for_id(&id))
+}
+
+/// Parse `input` as either a full ECHA registered-dossier url (via
+/// [`parse_dossier_url`]) or a bare numeric dossier id, as accepted by the
+/// `batch` targets list. Always returns a validated, canonical
+/// [`DossierRef`] instead of leaving each call site to re-derive one with
+/// its own `rsplit('/')`/digit-check.
+pub fn parse_dossier_target(input: &str) -> Result<DossierRef> {
+    let trimmed = input.trim();
+    if trimmed.contains("://") {
+        return parse_dossier_url(trimmed);
+    }
+
+    if !is_valid_dossier_id(trimmed) {
+        return Err(anyhow!(
+            "'{input}' is not a valid ECHA dossier url or numeric dossier id"
+        ));
+    }
+
+    Ok(dossier_ref_for_id(trimmed))
+}
+
+/// Returns whether `id` is a non-empty string of ASCII digits, the shape
+/// every ECHA registered-dossier id takes.
+pub fn is_valid_dossier_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())
+}
+
+fn dossier_ref_for_id(id: &str) -> DossierRef {
+    DossierRef {
+        id: id.to_string(),
+        canonical_url: format!(
+            "https://{ECHA_HOST}/registration-dossier/-/registered-dossier/{id}"
+        ),
+    }
+}
+
+/// Returns whether `input` parses as a well-formed `http`/`https` url with a
+/// host, without requiring it to point at [`ECHA_HOST`] specifically.
+///
+/// Used to sanity-check scraped fields (e.g. a constituent's `weblink`) that
+/// are rendered back out as an `href` rather than followed by this crate,
+/// so an unparsable or non-http value is dropped instead of emitted as-is.
+pub fn is_well_formed_http_url(input: &str) -> bool {
+    matches!(Url::parse(input), Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://echa.europa.eu/registration-dossier/-/registered-dossier/24529";
+
+    #[test]
+    fn parses_a_plain_dossier_url() {
+        let dossier_ref = parse_dossier_url(BASE).unwrap();
+        assert_eq!(dossier_ref.id, "24529");
+        assert_eq!(dossier_ref.canonical_url, BASE);
+    }
+
+    #[test]
+    fn ignores_a_trailing_slash() {
+        let dossier_ref = parse_dossier_url(&format!("{BASE}/")).unwrap();
+        assert_eq!(dossier_ref.id, "24529");
+    }
+
+    #[test]
+    fn ignores_a_query_string() {
+        let dossier_ref = parse_dossier_url(&format!("{BASE}?tab=composition")).unwrap();
+        assert_eq!(dossier_ref.id, "24529");
+    }
+
+    #[test]
+    fn ignores_a_fragment() {
+        let dossier_ref = parse_dossier_url(&format!("{BASE}#section-1")).unwrap();
+        assert_eq!(dossier_ref.id, "24529");
+    }
+
+    #[test]
+    fn accepts_a_mixed_case_host() {
+        let dossier_ref = parse_dossier_url(
+            "https://ECHA.Europa.EU/registration-dossier/-/registered-dossier/24529",
+        )
+        .unwrap();
+        assert_eq!(dossier_ref.id, "24529");
+        assert_eq!(dossier_ref.canonical_url, BASE);
+    }
+
+    #[test]
+    fn rejects_a_punycode_host_that_is_not_echa() {
+        // `xn--caf-dma.com` is the punycode form of `café.com`, not
+        // `echa.europa.eu`, and must be rejected like any other off-site host.
+        assert!(parse_dossier_url(
+            "https://xn--caf-dma.com/registration-dossier/-/registered-dossier/24529"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_a_non_echa_host() {
+        assert!(parse_dossier_url(
+            "https://example.com/registration-dossier/-/registered-dossier/24529"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_a_non_numeric_id() {
+        assert!(parse_dossier_url(
+            "https://echa.europa.eu/registration-dossier/-/registered-dossier/abc"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parse_dossier_target_accepts_a_bare_id() {
+        let dossier_ref = parse_dossier_target("24529").unwrap();
+        assert_eq!(dossier_ref.id, "24529");
+        assert_eq!(dossier_ref.canonical_url, BASE);
+    }
+
+    #[test]
+    fn parse_dossier_target_rejects_a_non_numeric_id() {
+        assert!(parse_dossier_target("24529/").is_err());
+    }
+
+    #[test]
+    fn well_formed_http_url_accepts_http_and_https() {
+        assert!(is_well_formed_http_url("https://example.com/page"));
+        assert!(is_well_formed_http_url("http://example.com/page"));
+    }
+
+    #[test]
+    fn well_formed_http_url_rejects_non_http_schemes_and_garbage() {
+        assert!(!is_well_formed_http_url("javascript:alert(1)"));
+        assert!(!is_well_formed_http_url("not a url"));
+    }
+}